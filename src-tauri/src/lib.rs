@@ -1,7 +1,8 @@
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BinaryHeap, HashSet};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BingoCard {
@@ -15,6 +16,7 @@ pub struct GenerationResult {
     pub number_distribution: Vec<(u32, usize)>,
     pub success: bool,
     pub message: String,
+    pub seed: u64,
 }
 
 /// Extract all winning lines from a bingo card
@@ -56,22 +58,80 @@ fn line_to_key(line: &[u32]) -> String {
         .join(",")
 }
 
+/// A candidate's Efraimidis-Spirakis sampling key, ranked in a bounded min-heap
+struct ReservoirKey {
+    key: f64,
+    idx: usize,
+}
+
+impl PartialEq for ReservoirKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for ReservoirKey {}
+
+impl PartialOrd for ReservoirKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ReservoirKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Reversed so `BinaryHeap` (a max-heap) behaves as a min-heap on `key`,
+        // letting us evict the smallest surviving key in O(log k).
+        other.key.partial_cmp(&self.key).unwrap()
+    }
+}
+
+/// Efraimidis-Spirakis weighted reservoir sampling: picks up to `k` distinct indices without replacement in one pass
+fn weighted_sample_indices(weights: &[f64], k: usize, rng: &mut impl Rng) -> Vec<usize> {
+    let mut heap: BinaryHeap<ReservoirKey> = BinaryHeap::with_capacity(k);
+
+    for (idx, &weight) in weights.iter().enumerate() {
+        if weight <= 0.0 {
+            continue;
+        }
+
+        let u: f64 = rng.gen_range(f64::EPSILON..1.0);
+        let key = u.powf(1.0 / weight);
+
+        if heap.len() < k {
+            heap.push(ReservoirKey { key, idx });
+        } else if key > heap.peek().unwrap().key {
+            heap.pop();
+            heap.push(ReservoirKey { key, idx });
+        }
+    }
+
+    heap.into_iter().map(|item| item.idx).collect()
+}
+
 /// Generate bingo cards with the specified constraints
 fn generate_bingo_cards(
     num_cards: usize,
     min_num: u32,
     max_num: u32,
     max_attempts: usize,
+    seed: u64,
+    weight_overrides: &[f64],
 ) -> GenerationResult {
     let numbers: Vec<u32> = (min_num..=max_num).collect();
     let num_range = numbers.len();
 
     // Each card has 16 cells, we have num_cards cards
-    // Target: each number should appear approximately (16 * num_cards) / num_range times
+    // Target: each number should appear proportionally to its weight override,
+    // e.g. (16 * num_cards) / num_range times when all weights are equal.
     let total_cells = 16 * num_cards;
-    let target_per_number = total_cells / num_range;
+    let override_sum: f64 = weight_overrides.iter().sum();
+    let target_per_number: Vec<f64> = weight_overrides
+        .iter()
+        .map(|&w| total_cells as f64 * w / override_sum)
+        .collect();
 
-    let mut rng = rand::thread_rng();
+    let mut rng = StdRng::seed_from_u64(seed);
     let mut best_result: Option<(Vec<BingoCard>, Vec<usize>)> = None;
     let mut best_variance = f64::MAX;
 
@@ -89,45 +149,26 @@ fn generate_bingo_cards(
             while !card_found && card_attempts < MAX_CARD_ATTEMPTS {
                 card_attempts += 1;
 
-                // Select 16 numbers for this card using weighted random selection
-                let mut selected: Vec<u32> = Vec::new();
-                let mut temp_counts = number_counts.clone();
-
-                while selected.len() < 16 {
-                    // Recalculate weights based on current temp_counts
-                    let current_weights: Vec<f64> = temp_counts
-                        .iter()
-                        .enumerate()
-                        .map(|(i, &c)| {
-                            if selected.contains(&numbers[i]) {
-                                0.0 // Can't select same number twice on same card
-                            } else {
-                                let diff = (target_per_number as f64) - (c as f64);
-                                (diff + 10.0).max(0.1)
-                            }
-                        })
-                        .collect();
-
-                    let total_weight: f64 = current_weights.iter().sum();
-                    if total_weight <= 0.0 {
-                        break;
-                    }
-
-                    let mut random_val = rng.gen::<f64>() * total_weight;
-                    for (i, &w) in current_weights.iter().enumerate() {
-                        random_val -= w;
-                        if random_val <= 0.0 {
-                            selected.push(numbers[i]);
-                            temp_counts[i] += 1;
-                            break;
-                        }
-                    }
-                }
-
-                if selected.len() != 16 {
+                // Select 16 distinct numbers for this card in a single pass via
+                // weighted reservoir sampling, weighted toward numbers that are
+                // currently under their (possibly overridden) target count.
+                let current_weights: Vec<f64> = number_counts
+                    .iter()
+                    .zip(target_per_number.iter())
+                    .zip(weight_overrides.iter())
+                    .map(|((&c, &target), &override_weight)| {
+                        let diff = target - (c as f64);
+                        (diff + 10.0).max(0.1) * override_weight
+                    })
+                    .collect();
+
+                let sampled_indices = weighted_sample_indices(&current_weights, 16, &mut rng);
+                if sampled_indices.len() != 16 {
                     continue;
                 }
 
+                let mut selected: Vec<u32> = sampled_indices.iter().map(|&i| numbers[i]).collect();
+
                 // Shuffle and arrange into 4x4 grid
                 selected.shuffle(&mut rng);
                 let card: [[u32; 4]; 4] = [
@@ -176,12 +217,14 @@ fn generate_bingo_cards(
         }
 
         if success {
-            // Calculate variance of distribution
-            let mean = number_counts.iter().sum::<usize>() as f64 / num_range as f64;
+            // Calculate variance against the (possibly weighted) per-number
+            // targets, so "balanced" means matching the requested proportions
+            // rather than strict uniformity.
             let variance: f64 = number_counts
                 .iter()
-                .map(|&c| {
-                    let diff = c as f64 - mean;
+                .zip(target_per_number.iter())
+                .map(|(&c, &target)| {
+                    let diff = c as f64 - target;
                     diff * diff
                 })
                 .sum::<f64>()
@@ -215,6 +258,7 @@ fn generate_bingo_cards(
                     "Successfully generated {} bingo cards with balanced distribution!",
                     num_cards
                 ),
+                seed,
             }
         }
         None => GenerationResult {
@@ -222,12 +266,21 @@ fn generate_bingo_cards(
             number_distribution: Vec::new(),
             success: false,
             message: "Failed to generate valid bingo cards. Try adjusting parameters.".to_string(),
+            seed,
         },
     }
 }
 
 #[tauri::command]
-fn generate_cards(num_cards: usize, min_num: u32, max_num: u32) -> GenerationResult {
+fn generate_cards(
+    num_cards: usize,
+    min_num: u32,
+    max_num: u32,
+    seed: Option<u64>,
+    weights: Option<Vec<(u32, f64)>>,
+) -> GenerationResult {
+    let seed = seed.unwrap_or_else(|| rand::thread_rng().gen::<u64>());
+
     // Validate inputs
     if max_num < min_num {
         return GenerationResult {
@@ -235,6 +288,7 @@ fn generate_cards(num_cards: usize, min_num: u32, max_num: u32) -> GenerationRes
             number_distribution: Vec::new(),
             success: false,
             message: "Maximum number must be greater than or equal to minimum number.".to_string(),
+            seed,
         };
     }
 
@@ -245,17 +299,397 @@ fn generate_cards(num_cards: usize, min_num: u32, max_num: u32) -> GenerationRes
             number_distribution: Vec::new(),
             success: false,
             message: "Number range must be at least 16 to fill a 4x4 card.".to_string(),
+            seed,
+        };
+    }
+
+    // Multipliers are capped well below f64::MAX so that `total_cells as f64 * w`
+    // (and the variance computed from it) can never overflow to infinity.
+    const MAX_WEIGHT_MULTIPLIER: f64 = 1.0e6;
+
+    // Start every number at a base weight of 1.0, then fold in the caller's
+    // per-number multipliers (e.g. 0.0 to exclude a number, >1.0 to favor it).
+    let mut weight_overrides = vec![1.0_f64; range];
+    if let Some(overrides) = weights {
+        for (number, multiplier) in overrides {
+            if number < min_num
+                || number > max_num
+                || !multiplier.is_finite()
+                || !(0.0..=MAX_WEIGHT_MULTIPLIER).contains(&multiplier)
+            {
+                return GenerationResult {
+                    cards: Vec::new(),
+                    number_distribution: Vec::new(),
+                    success: false,
+                    message: format!(
+                        "Weight overrides must reference numbers within range and use finite multipliers between 0 and {}.",
+                        MAX_WEIGHT_MULTIPLIER
+                    ),
+                    seed,
+                };
+            }
+            weight_overrides[(number - min_num) as usize] = multiplier;
+        }
+    }
+
+    if weight_overrides.iter().filter(|&&w| w > 0.0).count() < 16 {
+        return GenerationResult {
+            cards: Vec::new(),
+            number_distribution: Vec::new(),
+            success: false,
+            message: "At least 16 numbers must have a positive weight to fill a 4x4 card."
+                .to_string(),
+            seed,
+        };
+    }
+
+    generate_bingo_cards(num_cards, min_num, max_num, 50, seed, &weight_overrides)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CardSimulationStats {
+    pub card_id: usize,
+    pub win_probability: f64,
+    pub mean_draws_to_win: f64,
+    pub median_draws_to_win: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationResult {
+    pub card_stats: Vec<CardSimulationStats>,
+    pub tie_probability: f64,
+    pub trials: usize,
+    pub seed: u64,
+    pub success: bool,
+    pub message: String,
+}
+
+/// Middle value of `sorted_draws` (already sorted ascending), averaged across the two middle values when even-length
+fn median_draws(sorted_draws: &[usize]) -> f64 {
+    let len = sorted_draws.len();
+    if len == 0 {
+        return 0.0;
+    }
+
+    if len % 2 == 1 {
+        sorted_draws[len / 2] as f64
+    } else {
+        (sorted_draws[len / 2 - 1] + sorted_draws[len / 2]) as f64 / 2.0
+    }
+}
+
+/// Monte-Carlo simulation of `trials` bingo games against `cards`, estimating each card's win probability and draws-to-win
+fn simulate_bingo_games(
+    cards: &[BingoCard],
+    min_num: u32,
+    max_num: u32,
+    trials: usize,
+    seed: u64,
+) -> SimulationResult {
+    let pool: Vec<u32> = (min_num..=max_num).collect();
+    let card_lines: Vec<Vec<Vec<u32>>> = cards
+        .iter()
+        .map(|card| get_winning_lines(&card.cells))
+        .collect();
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut wins = vec![0usize; cards.len()];
+    let mut draws_to_win: Vec<Vec<usize>> = vec![Vec::new(); cards.len()];
+    let mut tie_trials = 0usize;
+    let mut draw_order = pool.clone();
+
+    for _trial in 0..trials {
+        draw_order.shuffle(&mut rng);
+
+        let mut drawn: HashSet<u32> = HashSet::new();
+        for (draw_idx, &number) in draw_order.iter().enumerate() {
+            drawn.insert(number);
+
+            let winners: Vec<usize> = card_lines
+                .iter()
+                .enumerate()
+                .filter(|(_, lines)| {
+                    lines
+                        .iter()
+                        .any(|line| line.iter().all(|n| drawn.contains(n)))
+                })
+                .map(|(i, _)| i)
+                .collect();
+
+            if !winners.is_empty() {
+                let draws_taken = draw_idx + 1;
+                if winners.len() > 1 {
+                    tie_trials += 1;
+                }
+                for &winner in &winners {
+                    wins[winner] += 1;
+                    draws_to_win[winner].push(draws_taken);
+                }
+                break;
+            }
+        }
+    }
+
+    let card_stats: Vec<CardSimulationStats> = cards
+        .iter()
+        .enumerate()
+        .map(|(i, card)| {
+            let mut sorted_draws = draws_to_win[i].clone();
+            sorted_draws.sort_unstable();
+
+            CardSimulationStats {
+                card_id: card.id,
+                win_probability: wins[i] as f64 / trials as f64,
+                mean_draws_to_win: if sorted_draws.is_empty() {
+                    0.0
+                } else {
+                    sorted_draws.iter().sum::<usize>() as f64 / sorted_draws.len() as f64
+                },
+                median_draws_to_win: median_draws(&sorted_draws),
+            }
+        })
+        .collect();
+
+    SimulationResult {
+        card_stats,
+        tie_probability: tie_trials as f64 / trials as f64,
+        trials,
+        seed,
+        success: true,
+        message: format!("Simulated {} trials across {} cards.", trials, cards.len()),
+    }
+}
+
+#[tauri::command]
+fn simulate_game(
+    cards: Vec<BingoCard>,
+    min_num: u32,
+    max_num: u32,
+    trials: usize,
+    seed: Option<u64>,
+) -> SimulationResult {
+    let seed = seed.unwrap_or_else(|| rand::thread_rng().gen::<u64>());
+
+    if cards.is_empty() {
+        return SimulationResult {
+            card_stats: Vec::new(),
+            tie_probability: 0.0,
+            trials,
+            seed,
+            success: false,
+            message: "At least one card is required to run a simulation.".to_string(),
+        };
+    }
+
+    if max_num < min_num {
+        return SimulationResult {
+            card_stats: Vec::new(),
+            tie_probability: 0.0,
+            trials,
+            seed,
+            success: false,
+            message: "Maximum number must be greater than or equal to minimum number.".to_string(),
+        };
+    }
+
+    if trials == 0 {
+        return SimulationResult {
+            card_stats: Vec::new(),
+            tie_probability: 0.0,
+            trials,
+            seed,
+            success: false,
+            message: "At least one trial is required to run a simulation.".to_string(),
+        };
+    }
+
+    let has_out_of_range_cell = cards
+        .iter()
+        .flat_map(|card| card.cells.iter().flatten())
+        .any(|&n| n < min_num || n > max_num);
+    if has_out_of_range_cell {
+        return SimulationResult {
+            card_stats: Vec::new(),
+            tie_probability: 0.0,
+            trials,
+            seed,
+            success: false,
+            message: "All card cells must fall within [min_num, max_num].".to_string(),
         };
     }
 
-    generate_bingo_cards(num_cards, min_num, max_num, 50)
+    simulate_bingo_games(&cards, min_num, max_num, trials, seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn weighted_sample_indices_returns_k_distinct_positive_weight_indices() {
+        let weights = vec![1.0, 0.0, 2.0, 3.0, 0.0, 4.0];
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let sampled = weighted_sample_indices(&weights, 4, &mut rng);
+
+        assert_eq!(sampled.len(), 4);
+        let unique: HashSet<usize> = sampled.iter().copied().collect();
+        assert_eq!(unique.len(), 4);
+        for idx in sampled {
+            assert!(weights[idx] > 0.0);
+        }
+    }
+
+    #[test]
+    fn weighted_sample_indices_caps_at_available_positive_weights() {
+        let weights = vec![1.0, 0.0, 0.0, 2.0];
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let sampled = weighted_sample_indices(&weights, 4, &mut rng);
+
+        assert_eq!(sampled.len(), 2);
+    }
+
+    #[test]
+    fn median_draws_on_odd_length() {
+        assert_eq!(median_draws(&[1, 2, 3]), 2.0);
+    }
+
+    #[test]
+    fn median_draws_on_even_length() {
+        assert_eq!(median_draws(&[1, 2, 3, 4]), 2.5);
+    }
+
+    #[test]
+    fn median_draws_on_empty() {
+        assert_eq!(median_draws(&[]), 0.0);
+    }
+
+    #[test]
+    fn generate_cards_same_seed_is_reproducible() {
+        let seed = Some(12345u64);
+        let first = generate_cards(4, 1, 20, seed, None);
+        let second = generate_cards(4, 1, 20, seed, None);
+
+        assert!(first.success);
+        assert_eq!(first.seed, second.seed);
+        assert_eq!(first.number_distribution, second.number_distribution);
+
+        let cells_a: Vec<_> = first.cards.iter().map(|c| c.cells).collect();
+        let cells_b: Vec<_> = second.cards.iter().map(|c| c.cells).collect();
+        assert_eq!(cells_a, cells_b);
+    }
+
+    #[test]
+    fn generate_cards_without_seed_still_reports_one() {
+        let result = generate_cards(4, 1, 20, None, None);
+        assert!(result.success);
+
+        let reproduced = generate_cards(4, 1, 20, Some(result.seed), None);
+        assert_eq!(
+            result.cards.iter().map(|c| c.cells).collect::<Vec<_>>(),
+            reproduced.cards.iter().map(|c| c.cells).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn generate_cards_weight_override_biases_distribution() {
+        let result = generate_cards(6, 1, 20, Some(99), Some(vec![(1, 5.0)]));
+        assert!(result.success);
+
+        let count_for_number_1 = result
+            .number_distribution
+            .iter()
+            .find(|&&(n, _)| n == 1)
+            .unwrap()
+            .1;
+        let others_mean = result
+            .number_distribution
+            .iter()
+            .filter(|&&(n, _)| n != 1)
+            .map(|&(_, c)| c)
+            .sum::<usize>() as f64
+            / 19.0;
+
+        assert!(count_for_number_1 as f64 > others_mean);
+    }
+
+    #[test]
+    fn generate_cards_zero_weight_excludes_number() {
+        let result = generate_cards(4, 1, 20, Some(55), Some(vec![(1, 0.0)]));
+        assert!(result.success);
+
+        let count_for_number_1 = result
+            .number_distribution
+            .iter()
+            .find(|&&(n, _)| n == 1)
+            .unwrap()
+            .1;
+        assert_eq!(count_for_number_1, 0);
+    }
+
+    fn full_house_card(id: usize) -> BingoCard {
+        BingoCard {
+            id,
+            cells: [
+                [1, 2, 3, 4],
+                [5, 6, 7, 8],
+                [9, 10, 11, 12],
+                [13, 14, 15, 16],
+            ],
+        }
+    }
+
+    #[test]
+    fn simulate_bingo_games_card_covering_the_whole_pool_always_wins() {
+        let result = simulate_bingo_games(&[full_house_card(1)], 1, 16, 50, 7);
+
+        assert!(result.success);
+        assert_eq!(result.trials, 50);
+        assert_eq!(result.card_stats.len(), 1);
+        assert_eq!(result.card_stats[0].win_probability, 1.0);
+        assert_eq!(result.tie_probability, 0.0);
+        assert!(result.card_stats[0].mean_draws_to_win <= 16.0);
+    }
+
+    #[test]
+    fn simulate_bingo_games_identical_cards_always_tie() {
+        let result = simulate_bingo_games(&[full_house_card(1), full_house_card(2)], 1, 16, 20, 3);
+
+        assert_eq!(result.tie_probability, 1.0);
+        assert_eq!(result.card_stats[0].win_probability, 1.0);
+        assert_eq!(result.card_stats[1].win_probability, 1.0);
+    }
+
+    #[test]
+    fn simulate_game_rejects_empty_cards() {
+        let result = simulate_game(vec![], 1, 20, 100, Some(1));
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn simulate_game_rejects_zero_trials() {
+        let result = simulate_game(vec![full_house_card(1)], 1, 16, 0, Some(1));
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn simulate_game_rejects_cells_outside_range() {
+        let mut card = full_house_card(1);
+        card.cells[3][3] = 99;
+
+        let result = simulate_game(vec![card], 1, 16, 10, Some(1));
+        assert!(!result.success);
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![generate_cards])
+        .invoke_handler(tauri::generate_handler![generate_cards, simulate_game])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }